@@ -0,0 +1,97 @@
+//! Middleware for adding a default, populated context to an incoming plain
+//! hyper request.
+
+use auth::{AuthData, Authorization};
+use context::{ContextualPayload, Push};
+use service::{NewService, Service};
+use hyper::{Body, Request};
+use std::io;
+use std::marker::PhantomData;
+use XSpanIdString;
+
+/// Middleware wrapper service, to build a `MakeAddContext` from an inner
+/// `NewService`.
+#[derive(Debug)]
+pub struct MakeAddContext<T, A> {
+    inner: T,
+    marker: PhantomData<A>,
+}
+
+impl<T, A> MakeAddContext<T, A> {
+    /// Create a new `MakeAddContext` struct wrapping the supplied inner
+    /// `NewService`.
+    pub fn new(inner: T) -> Self {
+        MakeAddContext {
+            inner,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, A, B, C> NewService for MakeAddContext<T, A>
+where
+    A: Default + Push<XSpanIdString, Result = B>,
+    B: Push<Option<AuthData>, Result = C>,
+    C: Push<Option<Authorization>>,
+    T: NewService<Request = Request<ContextualPayload<Body, C::Result>>>,
+{
+    type Request = Request<Body>;
+    type Response = T::Response;
+    type Error = T::Error;
+    type Instance = AddContext<T::Instance, A>;
+
+    fn new_service(&self) -> Result<Self::Instance, io::Error> {
+        self.inner.new_service().map(AddContext::new)
+    }
+}
+
+/// Middleware service which populates a context from an incoming hyper
+/// `Request`, before passing the request - now carrying the context in its
+/// body - to the wrapped inner service.
+#[derive(Debug)]
+pub struct AddContext<T, A> {
+    inner: T,
+    marker: PhantomData<A>,
+}
+
+impl<T, A> AddContext<T, A> {
+    /// Create a new `AddContext` struct wrapping the supplied inner service.
+    pub fn new(inner: T) -> Self {
+        AddContext {
+            inner,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, A, B, C> Service for AddContext<T, A>
+where
+    A: Default + Push<XSpanIdString, Result = B>,
+    B: Push<Option<AuthData>, Result = C>,
+    C: Push<Option<Authorization>>,
+    T: Service<Request = Request<ContextualPayload<Body, C::Result>>>,
+{
+    type Request = Request<Body>;
+    type Response = T::Response;
+    type Error = T::Error;
+    type Future = T::Future;
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        let x_span_id = XSpanIdString::get_or_generate(&req);
+        let auth_data = req.headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(AuthData::from_header);
+
+        let context = A::default()
+            .push(x_span_id)
+            .push(auth_data)
+            .push(None::<Authorization>);
+
+        let (parts, body) = req.into_parts();
+        self.inner.call(Request::from_parts(
+            parts,
+            ContextualPayload { inner: body, context },
+        ))
+    }
+}
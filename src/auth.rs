@@ -0,0 +1,286 @@
+//! Structures for handling authentication and authorization data.
+
+use context::{ContextualPayload, Has};
+use futures::future::{ok, Future};
+use service::{NewService, Service};
+use hyper::{Body, Request, Response, StatusCode};
+use std::collections::BTreeSet;
+use std::io;
+use std::marker::PhantomData;
+
+/// Authentication data, pulled from an incoming request, that can be used to
+/// authorize the request.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AuthData {
+    /// HTTP Basic auth.
+    Basic(String, String),
+    /// HTTP Bearer auth, i.e. a possibly opaque access token.
+    Bearer(String),
+    /// API key auth.
+    ApiKey(String),
+}
+
+impl AuthData {
+    /// Parse an `Authorization` header value into an `AuthData`, recognising
+    /// `Basic`, `Bearer`, and (as a fallback) a bare API key.
+    pub fn from_header(header: &str) -> Option<AuthData> {
+        let mut parts = header.splitn(2, ' ');
+        match (parts.next(), parts.next()) {
+            (Some(scheme), Some(value)) if scheme.eq_ignore_ascii_case("basic") => {
+                ::base64::decode(value).ok().and_then(|decoded| {
+                    String::from_utf8(decoded).ok().and_then(|decoded| {
+                        let mut decoded_parts = decoded.splitn(2, ':');
+                        match (decoded_parts.next(), decoded_parts.next()) {
+                            (Some(user), Some(pass)) => {
+                                Some(AuthData::Basic(user.to_string(), pass.to_string()))
+                            }
+                            _ => None,
+                        }
+                    })
+                })
+            }
+            (Some(scheme), Some(value)) if scheme.eq_ignore_ascii_case("bearer") => {
+                Some(AuthData::Bearer(value.to_string()))
+            }
+            (Some(value), None)
+                if !value.eq_ignore_ascii_case("basic") && !value.eq_ignore_ascii_case("bearer") =>
+            {
+                Some(AuthData::ApiKey(value.to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Authorization information resulting from authenticating a request, to be
+/// stored in the request context and consulted by downstream services.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Authorization {
+    /// Subject for which authorization is granted, i.e. the identity of the
+    /// caller.
+    pub subject: String,
+
+    /// Scopes for which authorization is granted.
+    pub scopes: Scopes,
+
+    /// Identity of the party that issued the authorization, if known.
+    pub issuer: Option<String>,
+}
+
+/// The set of scopes for which authorization has been granted.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Scopes {
+    /// Authorization has been granted for all scopes.
+    All,
+    /// Authorization has been granted for the enclosed set of scopes.
+    Some(BTreeSet<String>),
+}
+
+impl Scopes {
+    /// Returns whether every scope in `required` is covered by `self`.
+    pub fn grants_all<'a, I>(&self, required: I) -> bool
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        match *self {
+            Scopes::All => true,
+            Scopes::Some(ref granted) => required.into_iter().all(|s| granted.contains(s)),
+        }
+    }
+}
+
+/// Middleware wrapper service, to build a `MakeCheckScopes` from an inner
+/// `NewService`.
+#[derive(Debug)]
+pub struct MakeCheckScopes<T, C> {
+    inner: T,
+    required_scopes: BTreeSet<String>,
+    marker: PhantomData<C>,
+}
+
+impl<T, C> MakeCheckScopes<T, C> {
+    /// Create a new `MakeCheckScopes` struct wrapping the supplied inner
+    /// `NewService`, requiring that every scope in `required_scopes` be
+    /// present on the authenticated caller.
+    pub fn new<I>(inner: T, required_scopes: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        MakeCheckScopes {
+            inner,
+            required_scopes: required_scopes.into_iter().collect(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, C> NewService for MakeCheckScopes<T, C>
+where
+    C: Has<Option<Authorization>>,
+    T: NewService<Request = Request<ContextualPayload<Body, C>>, Response = Response<Body>, Error = ::hyper::Error>,
+    T::Instance: 'static,
+    <T::Instance as Service>::Future: 'static,
+{
+    type Request = Request<ContextualPayload<Body, C>>;
+    type Response = Response<Body>;
+    type Error = ::hyper::Error;
+    type Instance = CheckScopes<T::Instance, C>;
+
+    fn new_service(&self) -> Result<Self::Instance, io::Error> {
+        self.inner.new_service().map(|inner| {
+            CheckScopes::new(inner, self.required_scopes.clone())
+        })
+    }
+}
+
+/// Middleware service which checks that the `Authorization` stored in the
+/// request context grants every one of `required_scopes`, before forwarding
+/// the request unchanged to the wrapped inner service. Responds with
+/// `401 Unauthorized` if there is no `Authorization` in the context, or
+/// `403 Forbidden` if it is missing one or more of the required scopes.
+#[derive(Debug)]
+pub struct CheckScopes<T, C> {
+    inner: T,
+    required_scopes: BTreeSet<String>,
+    marker: PhantomData<C>,
+}
+
+impl<T, C> CheckScopes<T, C> {
+    /// Create a new `CheckScopes` struct wrapping the supplied inner service.
+    pub fn new(inner: T, required_scopes: BTreeSet<String>) -> Self {
+        CheckScopes {
+            inner,
+            required_scopes,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, C> Service for CheckScopes<T, C>
+where
+    C: Has<Option<Authorization>>,
+    T: Service<Request = Request<ContextualPayload<Body, C>>, Response = Response<Body>, Error = ::hyper::Error>
+        + 'static,
+    T::Future: 'static,
+{
+    type Request = Request<ContextualPayload<Body, C>>;
+    type Response = Response<Body>;
+    type Error = ::hyper::Error;
+    type Future = Box<Future<Item = Response<Body>, Error = ::hyper::Error>>;
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        let authorization = Has::<Option<Authorization>>::get(req.body().context()).clone();
+
+        match authorization {
+            None => Box::new(ok(Response::builder()
+                .status(StatusCode::UNAUTHORIZED)
+                .body(Body::empty())
+                .unwrap())),
+            Some(ref authorization) if !authorization.scopes.grants_all(self.required_scopes.iter().map(String::as_str)) => {
+                Box::new(ok(Response::builder()
+                    .status(StatusCode::FORBIDDEN)
+                    .body(Body::empty())
+                    .unwrap()))
+            }
+            Some(_) => Box::new(self.inner.call(req)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context::Push;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn from_header_parses_basic() {
+        let header = format!("Basic {}", ::base64::encode("Aladdin:open sesame"));
+        assert_eq!(
+            AuthData::from_header(&header),
+            Some(AuthData::Basic("Aladdin".to_string(), "open sesame".to_string()))
+        );
+    }
+
+    #[test]
+    fn from_header_parses_bearer() {
+        assert_eq!(
+            AuthData::from_header("Bearer mF_9.B5f-4.1JqM"),
+            Some(AuthData::Bearer("mF_9.B5f-4.1JqM".to_string()))
+        );
+    }
+
+    #[test]
+    fn from_header_parses_api_key() {
+        assert_eq!(
+            AuthData::from_header("abc123"),
+            Some(AuthData::ApiKey("abc123".to_string()))
+        );
+    }
+
+    #[test]
+    fn from_header_rejects_bare_scheme_keyword() {
+        assert_eq!(AuthData::from_header("Bearer"), None);
+        assert_eq!(AuthData::from_header("Basic"), None);
+    }
+
+    new_context_type!(TestContext, TestEmptyContext, Option<Authorization>);
+
+    type Ctx = make_context_ty!(TestContext, TestEmptyContext, Option<Authorization>);
+
+    struct OkService;
+
+    impl Service for OkService {
+        type Request = Request<ContextualPayload<Body, Ctx>>;
+        type Response = Response<Body>;
+        type Error = ::hyper::Error;
+        type Future = Box<Future<Item = Response<Body>, Error = ::hyper::Error>>;
+
+        fn call(&self, _req: Self::Request) -> Self::Future {
+            Box::new(ok(Response::builder()
+                .status(StatusCode::OK)
+                .body(Body::empty())
+                .unwrap()))
+        }
+    }
+
+    fn required_scopes() -> BTreeSet<String> {
+        vec!["read".to_string()].into_iter().collect()
+    }
+
+    fn request_with(auth: Option<Authorization>) -> Request<ContextualPayload<Body, Ctx>> {
+        let context = make_context!(TestContext, TestEmptyContext, auth);
+        let (parts, _) = Request::new(Body::empty()).into_parts();
+        Request::from_parts(parts, ContextualPayload { inner: Body::empty(), context })
+    }
+
+    fn status_for(auth: Option<Authorization>) -> StatusCode {
+        let middleware = CheckScopes::new(OkService, required_scopes());
+        middleware.call(request_with(auth)).wait().unwrap().status()
+    }
+
+    #[test]
+    fn rejects_missing_authorization() {
+        assert_eq!(status_for(None), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn rejects_missing_scope() {
+        let authorization = Authorization {
+            subject: "user".to_string(),
+            scopes: Scopes::Some(vec!["write".to_string()].into_iter().collect()),
+            issuer: None,
+        };
+        assert_eq!(status_for(Some(authorization)), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn passes_through_when_scope_granted() {
+        let authorization = Authorization {
+            subject: "user".to_string(),
+            scopes: Scopes::All,
+            issuer: None,
+        };
+        assert_eq!(status_for(Some(authorization)), StatusCode::OK);
+    }
+}
@@ -0,0 +1,188 @@
+//! A service that can dispatch to one of several mounted services, based on
+//! the path prefix of the incoming request.
+
+use context::ContextualPayload;
+use futures::future::{ok, Future};
+use service::{NewService, Service};
+use hyper::{Body, Request, Response, StatusCode};
+use std::io;
+
+type CompositeRequest<C> = Request<ContextualPayload<Body, C>>;
+type BoxedFuture = Box<Future<Item = Response<Body>, Error = ::hyper::Error>>;
+type BoxedService<C> = Box<Service<Request = CompositeRequest<C>, Response = Response<Body>, Error = ::hyper::Error, Future = BoxedFuture>>;
+type BoxedMakeService<C> = Box<NewService<Request = CompositeRequest<C>, Response = Response<Body>, Error = ::hyper::Error, Instance = BoxedService<C>>>;
+
+/// A `NewService` that holds a list of `(base_path, service)` entries, and
+/// dispatches each incoming request to the first entry whose `base_path`
+/// prefixes the request's path, falling back to a `404 Not Found` if no
+/// entry matches.
+///
+/// All mounted services must share the same context type `C`, so that a
+/// `CompositeMakeService` can itself be wrapped by `MakeAddContext`.
+pub struct CompositeMakeService<C> {
+    services: Vec<(&'static str, BoxedMakeService<C>)>,
+}
+
+impl<C> Default for CompositeMakeService<C> {
+    fn default() -> Self {
+        CompositeMakeService { services: vec![] }
+    }
+}
+
+impl<C> CompositeMakeService<C> {
+    /// Create an empty `CompositeMakeService`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mount a service under the given base path. Requests are matched
+    /// against entries in the order they were pushed, so more specific
+    /// prefixes should be pushed before less specific ones.
+    pub fn push<S>(&mut self, entry: (&'static str, S))
+    where
+        S: NewService<Request = CompositeRequest<C>, Response = Response<Body>, Error = ::hyper::Error> + 'static,
+        S::Instance: Service<Request = CompositeRequest<C>, Response = Response<Body>, Error = ::hyper::Error, Future = BoxedFuture> + 'static,
+    {
+        let (base_path, service) = entry;
+        self.services.push((base_path, Box::new(BoxingNewService(service))));
+    }
+}
+
+impl<C: 'static> NewService for CompositeMakeService<C> {
+    type Request = CompositeRequest<C>;
+    type Response = Response<Body>;
+    type Error = ::hyper::Error;
+    type Instance = CompositeService<C>;
+
+    fn new_service(&self) -> Result<Self::Instance, io::Error> {
+        let services = self.services
+            .iter()
+            .map(|&(base_path, ref new_service)| new_service.new_service().map(|s| (base_path, s)))
+            .collect::<Result<_, _>>()?;
+        Ok(CompositeService { services })
+    }
+}
+
+/// The `Service` created by a `CompositeMakeService`.
+pub struct CompositeService<C> {
+    services: Vec<(&'static str, BoxedService<C>)>,
+}
+
+impl<C> Service for CompositeService<C> {
+    type Request = CompositeRequest<C>;
+    type Response = Response<Body>;
+    type Error = ::hyper::Error;
+    type Future = BoxedFuture;
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        let path = req.uri().path().to_owned();
+        for &(base_path, ref service) in &self.services {
+            if path.starts_with(base_path) {
+                return service.call(req);
+            }
+        }
+        Box::new(ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap()))
+    }
+}
+
+/// Adapter that boxes the `Instance`/`Future` of a wrapped `NewService`, so
+/// that services with different concrete types can be stored side by side in
+/// a `CompositeMakeService`.
+struct BoxingNewService<S>(S);
+
+impl<S, C> NewService for BoxingNewService<S>
+where
+    S: NewService<Request = CompositeRequest<C>, Response = Response<Body>, Error = ::hyper::Error>,
+    S::Instance: Service<Request = CompositeRequest<C>, Response = Response<Body>, Error = ::hyper::Error, Future = BoxedFuture> + 'static,
+{
+    type Request = CompositeRequest<C>;
+    type Response = Response<Body>;
+    type Error = ::hyper::Error;
+    type Instance = BoxedService<C>;
+
+    fn new_service(&self) -> Result<Self::Instance, io::Error> {
+        self.0.new_service().map(|s| Box::new(s) as Self::Instance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::Stream;
+
+    struct TaggedService(&'static str);
+
+    impl Service for TaggedService {
+        type Request = CompositeRequest<()>;
+        type Response = Response<Body>;
+        type Error = ::hyper::Error;
+        type Future = BoxedFuture;
+
+        fn call(&self, _req: Self::Request) -> Self::Future {
+            Box::new(ok(Response::builder()
+                .status(StatusCode::OK)
+                .body(Body::from(self.0))
+                .unwrap()))
+        }
+    }
+
+    struct TaggedNewService(&'static str);
+
+    impl NewService for TaggedNewService {
+        type Request = CompositeRequest<()>;
+        type Response = Response<Body>;
+        type Error = ::hyper::Error;
+        type Instance = TaggedService;
+
+        fn new_service(&self) -> Result<Self::Instance, io::Error> {
+            Ok(TaggedService(self.0))
+        }
+    }
+
+    fn request_for(path: &str) -> CompositeRequest<()> {
+        let uri: ::hyper::Uri = path.parse().unwrap();
+        let (mut parts, _) = Request::new(Body::empty()).into_parts();
+        parts.uri = uri;
+        Request::from_parts(parts, ContextualPayload { inner: Body::empty(), context: () })
+    }
+
+    fn body_string(response: Response<Body>) -> String {
+        let body = response.into_body().concat2().wait().unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn dispatches_to_matching_prefix() {
+        let mut make_service = CompositeMakeService::new();
+        make_service.push(("/foo", TaggedNewService("foo")));
+        make_service.push(("/bar", TaggedNewService("bar")));
+        let service = make_service.new_service().unwrap();
+
+        let response = service.call(request_for("/bar/baz")).wait().unwrap();
+        assert_eq!(body_string(response), "bar");
+    }
+
+    #[test]
+    fn first_match_wins_when_prefixes_overlap() {
+        let mut make_service = CompositeMakeService::new();
+        make_service.push(("/foo/specific", TaggedNewService("specific")));
+        make_service.push(("/foo", TaggedNewService("general")));
+        let service = make_service.new_service().unwrap();
+
+        let response = service.call(request_for("/foo/specific/thing")).wait().unwrap();
+        assert_eq!(body_string(response), "specific");
+    }
+
+    #[test]
+    fn falls_back_to_404_when_nothing_matches() {
+        let mut make_service = CompositeMakeService::new();
+        make_service.push(("/foo", TaggedNewService("foo")));
+        let service = make_service.new_service().unwrap();
+
+        let response = service.call(request_for("/unmounted")).wait().unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -18,7 +18,9 @@ use super::XSpanIdString;
 /// # extern crate futures;
 /// #
 /// # use swagger::context::*;
+/// # use swagger::service::Service;
 /// # use futures::future::{Future, ok};
+/// # use hyper::{Body, Request, Response};
 /// # use std::marker::PhantomData;
 /// #
 /// # struct MyItem;
@@ -28,16 +30,16 @@ use super::XSpanIdString;
 ///     marker: PhantomData<C>,
 /// }
 ///
-/// impl<C> hyper::server::Service for MyService<C>
+/// impl<C> Service for MyService<C>
 ///     where C: Has<MyItem>,
 /// {
-///     type Request = (hyper::Request, C);
-///     type Response = hyper::Response;
+///     type Request = Request<ContextualPayload<Body, C>>;
+///     type Response = Response<Body>;
 ///     type Error = hyper::Error;
 ///     type Future = Box<Future<Item=Self::Response, Error=Self::Error>>;
-///     fn call(&self, (req, context) : Self::Request) -> Self::Future {
-///         do_something_with_my_item(Has::<MyItem>::get(&context));
-///         Box::new(ok(hyper::Response::new()))
+///     fn call(&self, req: Self::Request) -> Self::Future {
+///         do_something_with_my_item(Has::<MyItem>::get(req.body().context()));
+///         Box::new(ok(Response::new(Body::empty())))
 ///     }
 /// }
 ///
@@ -293,13 +295,77 @@ where
     }
 }
 
+/// A `hyper::Payload` that carries a context `C` alongside the request/response
+/// body stream `S`.
+///
+/// This lets services thread a context through a single `hyper::Request`
+/// (`Request<ContextualPayload<Body, C>>`) rather than a `(Request, C)` tuple,
+/// so they can implement hyper's own `Service` trait and interoperate with
+/// generic tower/hyper middleware. Every `Stream`/`Payload` method is
+/// forwarded to `inner`; only the context accessors are new.
+#[derive(Debug)]
+pub struct ContextualPayload<S, C> {
+    /// The wrapped body stream.
+    pub inner: S,
+    /// The context being carried alongside the body.
+    pub context: C,
+}
+
+impl<S, C> ContextualPayload<S, C> {
+    /// Borrows the context.
+    pub fn context(&self) -> &C {
+        &self.context
+    }
+
+    /// Mutably borrows the context.
+    pub fn context_mut(&mut self) -> &mut C {
+        &mut self.context
+    }
+}
+
+impl<S, C> ::futures::Stream for ContextualPayload<S, C>
+where
+    S: ::futures::Stream,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> ::futures::Poll<Option<Self::Item>, Self::Error> {
+        self.inner.poll()
+    }
+}
+
+impl<S, C> ::hyper::body::Payload for ContextualPayload<S, C>
+where
+    S: ::hyper::body::Payload,
+    C: Send + 'static,
+{
+    type Data = S::Data;
+    type Error = S::Error;
+
+    fn poll_data(&mut self) -> ::futures::Poll<Option<Self::Data>, Self::Error> {
+        self.inner.poll_data()
+    }
+
+    fn poll_trailers(&mut self) -> ::futures::Poll<Option<::hyper::HeaderMap>, Self::Error> {
+        self.inner.poll_trailers()
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn content_length(&self) -> Option<u64> {
+        self.inner.content_length()
+    }
+}
+
 #[cfg(test)]
 mod context_tests {
-    use hyper::server::{NewService, Service};
-    use hyper::{Response, Request, Error, Method, Uri};
+    use service::{NewService, Service};
+    use hyper::{Body, Response, Request, Error, Method};
     use std::marker::PhantomData;
     use std::io;
-    use std::str::FromStr;
     use futures::future::{Future, ok};
     use super::*;
 
@@ -320,13 +386,13 @@ mod context_tests {
     where
         C: Has<ContextItem2>,
     {
-        type Request = (Request, C);
-        type Response = Response;
+        type Request = Request<ContextualPayload<Body, C>>;
+        type Response = Response<Body>;
         type Error = Error;
-        type Future = Box<Future<Item = Response, Error = Error>>;
-        fn call(&self, (_, context): Self::Request) -> Self::Future {
-            do_something_with_item_2(Has::<ContextItem2>::get(&context));
-            Box::new(ok(Response::new()))
+        type Future = Box<Future<Item = Response<Body>, Error = Error>>;
+        fn call(&self, req: Self::Request) -> Self::Future {
+            do_something_with_item_2(Has::<ContextItem2>::get(req.body().context()));
+            Box::new(ok(Response::new(Body::empty())))
         }
     }
 
@@ -350,8 +416,8 @@ mod context_tests {
     where
         C: Has<ContextItem2>,
     {
-        type Request = (Request, C);
-        type Response = Response;
+        type Request = Request<ContextualPayload<Body, C>>;
+        type Response = Response<Body>;
         type Error = Error;
         type Instance = InnerService<C>;
         fn new_service(&self) -> Result<Self::Instance, io::Error> {
@@ -363,7 +429,7 @@ mod context_tests {
     where
         C: Pop<ContextItem1>,
         C::Result : Push<ContextItem2>,
-        T: Service<Request = (Request, <C::Result as Push<ContextItem2>>::Result)>,
+        T: Service<Request = Request<ContextualPayload<Body, <C::Result as Push<ContextItem2>>::Result>>>,
     {
         inner: T,
         marker1: PhantomData<C>,
@@ -373,17 +439,18 @@ mod context_tests {
     where
         C: Pop<ContextItem1>,
         C::Result : Push<ContextItem2>,
-        T: Service<Request = (Request, <C::Result as Push<ContextItem2>>::Result)>,
+        T: Service<Request = Request<ContextualPayload<Body, <C::Result as Push<ContextItem2>>::Result>>>,
     {
-        type Request = (Request, C);
+        type Request = Request<ContextualPayload<Body, C>>;
         type Response = T::Response;
         type Error = T::Error;
         type Future = T::Future;
-        fn call(&self, (req, context): Self::Request) -> Self::Future {
-            let (item, context) = context.pop();
+        fn call(&self, req: Self::Request) -> Self::Future {
+            let (parts, payload) = req.into_parts();
+            let (item, context) = payload.context.pop();
             do_something_with_item_1(&item);
             let context = context.push(ContextItem2 {});
-            self.inner.call((req, context))
+            self.inner.call(Request::from_parts(parts, ContextualPayload { inner: payload.inner, context }))
         }
     }
 
@@ -391,7 +458,7 @@ mod context_tests {
     where
         C: Pop<ContextItem1>,
         C::Result : Push<ContextItem2>,
-        T: NewService<Request = (Request, <C::Result as Push<ContextItem2>>::Result)>,
+        T: NewService<Request = Request<ContextualPayload<Body, <C::Result as Push<ContextItem2>>::Result>>>,
     {
         inner: T,
         marker1: PhantomData<C>,
@@ -401,9 +468,9 @@ mod context_tests {
     where
         C: Pop<ContextItem1>,
         C::Result : Push<ContextItem2>,
-        T: NewService<Request = (Request, <C::Result as Push<ContextItem2>>::Result)>,
+        T: NewService<Request = Request<ContextualPayload<Body, <C::Result as Push<ContextItem2>>::Result>>>,
     {
-        type Request = (Request, C);
+        type Request = Request<ContextualPayload<Body, C>>;
         type Response = T::Response;
         type Error = T::Error;
         type Instance = MiddleService<T::Instance, C>;
@@ -421,7 +488,7 @@ mod context_tests {
     where
         C: Pop<ContextItem1>,
         C::Result : Push<ContextItem2>,
-        T: NewService<Request = (Request, <C::Result as Push<ContextItem2>>::Result)>,
+        T: NewService<Request = Request<ContextualPayload<Body, <C::Result as Push<ContextItem2>>::Result>>>,
     {
         fn new(inner: T) -> Self {
             MiddleNewService {
@@ -434,7 +501,7 @@ mod context_tests {
     struct OuterService<T, C>
     where
         C: Default + Push<ContextItem1>,
-        T: Service<Request = (Request, C::Result)>,
+        T: Service<Request = Request<ContextualPayload<Body, C::Result>>>,
     {
         inner: T,
         marker: PhantomData<C>,
@@ -443,22 +510,23 @@ mod context_tests {
     impl<T, C> Service for OuterService<T, C>
     where
         C: Default + Push<ContextItem1>,
-        T: Service<Request = (Request, C::Result)>,
+        T: Service<Request = Request<ContextualPayload<Body, C::Result>>>,
     {
-        type Request = Request;
+        type Request = Request<Body>;
         type Response = T::Response;
         type Error = T::Error;
         type Future = T::Future;
         fn call(&self, req: Self::Request) -> Self::Future {
             let context = C::default().push(ContextItem1 {});
-            self.inner.call((req, context))
+            let (parts, body) = req.into_parts();
+            self.inner.call(Request::from_parts(parts, ContextualPayload { inner: body, context }))
         }
     }
 
     struct OuterNewService<T, C>
     where
         C: Default + Push<ContextItem1>,
-        T: NewService<Request = (Request, C::Result)>,
+        T: NewService<Request = Request<ContextualPayload<Body, C::Result>>>,
     {
         inner: T,
         marker: PhantomData<C>,
@@ -467,9 +535,9 @@ mod context_tests {
     impl<T, C> NewService for OuterNewService<T, C>
     where
         C: Default + Push<ContextItem1>,
-        T: NewService<Request = (Request, C::Result)>,
+        T: NewService<Request = Request<ContextualPayload<Body, C::Result>>>,
     {
-        type Request = Request;
+        type Request = Request<Body>;
         type Response = T::Response;
         type Error = T::Error;
         type Instance = OuterService<T::Instance, C>;
@@ -486,7 +554,7 @@ mod context_tests {
     impl<T, C> OuterNewService<T, C>
     where
         C: Default + Push<ContextItem1>,
-        T: NewService<Request = (Request, C::Result)>,
+        T: NewService<Request = Request<ContextualPayload<Body, C::Result>>>,
     {
         fn new(inner: T) -> Self {
             OuterNewService {
@@ -505,7 +573,11 @@ mod context_tests {
         let new_service =
             OuterNewService::<_, MyEmptyContext>::new(MiddleNewService::new(InnerNewService::new()));
 
-        let req = Request::new(Method::Post, Uri::from_str("127.0.0.1:80").unwrap());
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://127.0.0.1:80")
+            .body(Body::empty())
+            .unwrap();
         new_service
             .new_service()
             .expect("Failed to start new service")
@@ -0,0 +1,77 @@
+//! Middleware for dropping a context from a request, so that plain hyper
+//! services can be wrapped by context-aware middleware.
+
+use context::ContextualPayload;
+use service::{NewService, Service};
+use hyper::{Body, Request};
+use std::io;
+use std::marker::PhantomData;
+
+/// Wrapper for a `NewService`, to let a service that only knows about plain
+/// `hyper::Request<Body>`s be mounted behind middleware that threads a
+/// context `C` through the request body.
+#[derive(Debug)]
+pub struct DropContextMakeService<T, C> {
+    inner: T,
+    marker: PhantomData<C>,
+}
+
+impl<T, C> DropContextMakeService<T, C> {
+    /// Create a new `DropContextMakeService` struct wrapping the supplied
+    /// inner `NewService`.
+    pub fn new(inner: T) -> Self {
+        DropContextMakeService {
+            inner,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, C> NewService for DropContextMakeService<T, C>
+where
+    T: NewService<Request = Request<Body>>,
+{
+    type Request = Request<ContextualPayload<Body, C>>;
+    type Response = T::Response;
+    type Error = T::Error;
+    type Instance = DropContextService<T::Instance, C>;
+
+    fn new_service(&self) -> Result<Self::Instance, io::Error> {
+        self.inner.new_service().map(DropContextService::new)
+    }
+}
+
+/// Wrapper for a `Service`, which discards the context carried in an
+/// incoming request's body before forwarding the bare request to the wrapped
+/// inner service.
+#[derive(Debug)]
+pub struct DropContextService<T, C> {
+    inner: T,
+    marker: PhantomData<C>,
+}
+
+impl<T, C> DropContextService<T, C> {
+    /// Create a new `DropContextService` struct wrapping the supplied inner
+    /// service.
+    pub fn new(inner: T) -> Self {
+        DropContextService {
+            inner,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, C> Service for DropContextService<T, C>
+where
+    T: Service<Request = Request<Body>>,
+{
+    type Request = Request<ContextualPayload<Body, C>>;
+    type Response = T::Response;
+    type Error = T::Error;
+    type Future = T::Future;
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        let (parts, payload) = req.into_parts();
+        self.inner.call(Request::from_parts(parts, payload.inner))
+    }
+}
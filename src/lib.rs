@@ -0,0 +1,62 @@
+//! Support crate for Swagger codegen
+//!
+//! This crate provides an interface that can be used to build servers and
+//! clients that communicate over HTTP, along with supporting middleware for
+//! weaving in request-scoped context (authentication data, span IDs, etc.)
+//! on top of hyper.
+
+extern crate base64;
+extern crate bytes;
+extern crate futures;
+extern crate hyper;
+extern crate uuid;
+
+#[macro_use]
+pub mod context;
+pub mod add_context;
+pub mod auth;
+pub mod composite;
+pub mod drop_context;
+pub mod service;
+
+pub use add_context::{AddContext, MakeAddContext};
+pub use auth::{AuthData, Authorization, CheckScopes, MakeCheckScopes};
+pub use composite::{CompositeMakeService, CompositeService};
+pub use drop_context::{DropContextMakeService, DropContextService};
+pub use context::{ContextWrapper, ContextWrapperExt, Has, Pop, Push};
+pub use service::{NewService, Service};
+
+use std::ops::Deref;
+use uuid::Uuid;
+
+/// Very simple "generate a random span ID" implementation, used to populate
+/// the X-Span-ID header when one isn't already present on an incoming
+/// request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XSpanIdString(pub String);
+
+impl XSpanIdString {
+    /// Create a new `XSpanIdString` from the request's `X-Span-ID` header,
+    /// generating a new UUID if that header is absent, not valid text, or
+    /// empty.
+    pub fn get_or_generate<B>(req: &hyper::Request<B>) -> Self {
+        let x_span_id = req.headers()
+            .get("X-Span-ID")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned)
+            .unwrap_or_default();
+
+        if x_span_id.is_empty() {
+            XSpanIdString(Uuid::new_v4().to_hyphenated().to_string())
+        } else {
+            XSpanIdString(x_span_id)
+        }
+    }
+}
+
+impl Deref for XSpanIdString {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
@@ -0,0 +1,51 @@
+//! A small, hyper-version-agnostic service abstraction.
+//!
+//! hyper's own `Service`/`NewService` traits are parameterized over request
+//! and response *body* types rather than over the whole `Request`/`Response`,
+//! which makes them awkward to use for middleware that changes the `Request`
+//! type itself (e.g. by attaching a context). The middleware in this crate is
+//! built against the traits below instead.
+
+use futures::Future;
+use std::io;
+
+/// An asynchronous function from a `Request` to a `Response`.
+pub trait Service {
+    /// Requests handled by this service.
+    type Request;
+    /// Responses given by this service.
+    type Response;
+    /// Errors produced by this service.
+    type Error;
+    /// The future for the response, returned by `call`.
+    type Future: Future<Item = Self::Response, Error = Self::Error>;
+
+    /// Process the request and return the response asynchronously.
+    fn call(&self, req: Self::Request) -> Self::Future;
+}
+
+/// Creates new `Service` values.
+pub trait NewService {
+    /// Requests handled by the service.
+    type Request;
+    /// Responses given by the service.
+    type Response;
+    /// Errors produced by the service.
+    type Error;
+    /// The `Service` value created by this factory.
+    type Instance: Service<Request = Self::Request, Response = Self::Response, Error = Self::Error>;
+
+    /// Create and return a new service value.
+    fn new_service(&self) -> Result<Self::Instance, io::Error>;
+}
+
+impl<S: Service + ?Sized> Service for Box<S> {
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        (**self).call(req)
+    }
+}